@@ -6,11 +6,13 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use crate::demo::gameevent_gen::{
     GameEvent, PlayerDeathEvent, PlayerSpawnEvent, TeamPlayRoundWinEvent,
 };
+use crate::demo::header::Header;
 use crate::demo::message::packetentities::EntityId;
 use crate::demo::message::usermessage::{ChatMessageKind, SayText2Message, UserMessage};
 use crate::demo::message::{Message, MessageType};
 use crate::demo::packet::stringtable::StringTableEntry;
 use crate::demo::parser::handler::{BorrowMessageHandler, MessageHandler};
+use crate::demo::parser::protocolversion::ProtocolVersion;
 use crate::demo::vector::Vector;
 use crate::{ParserState, ReadResult, Stream};
 use num_enum::TryFromPrimitive;
@@ -22,20 +24,270 @@ pub struct ChatMassage {
     pub kind: ChatMessageKind,
     pub from: String,
     pub text: String,
+    pub segments: Vec<ChatSegment>,
     pub tick: u32,
 }
 
 impl ChatMassage {
-    pub fn from_message(message: &SayText2Message, tick: u32) -> Self {
+    pub fn from_message(message: &SayText2Message, tick: u32, team: Team) -> Self {
+        ChatMassage::from_parts(
+            message.kind,
+            message.from.clone().unwrap_or_default(),
+            message.text.clone(),
+            tick,
+            team,
+        )
+    }
+
+    /// Build a `ChatMassage` from already-split-out fields. Kept separate so
+    /// it can be exercised directly in tests without a real `SayText2Message`.
+    fn from_parts(kind: ChatMessageKind, from: String, text: String, tick: u32, team: Team) -> Self {
+        let segments = ChatSegment::parse(&text, team);
         ChatMassage {
-            kind: message.kind,
-            from: message.from.clone().unwrap_or_default(),
-            text: message.text.clone(),
+            kind,
+            from,
+            text,
+            segments,
             tick,
         }
     }
 }
 
+/// A single run of chat text sharing one color, as produced by splitting a
+/// `SayText2` string on its embedded color control bytes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatSegment {
+    pub color: Option<ChatColor>,
+    pub text: String,
+}
+
+/// An `RRGGBBAA` color as used by the `0x07`/`0x08` chat color control bytes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChatColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl ChatColor {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        ChatColor { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        ChatColor { r, g, b, a }
+    }
+}
+
+const CHAT_COLOR_DEFAULT: char = '\u{1}';
+const CHAT_COLOR_TEAM: char = '\u{3}';
+const CHAT_COLOR_LOCATION: char = '\u{4}';
+const CHAT_COLOR_ACHIEVEMENT: char = '\u{5}';
+const CHAT_COLOR_CUSTOM_RGB: char = '\u{7}';
+const CHAT_COLOR_CUSTOM_RGBA: char = '\u{8}';
+
+const LOCATION_CHAT_COLOR: ChatColor = ChatColor::rgb(153, 204, 255);
+const ACHIEVEMENT_CHAT_COLOR: ChatColor = ChatColor::rgb(255, 215, 0);
+
+/// Resolve the color `0x03` stands for: the speaking player's team color.
+fn team_chat_color(team: Team) -> ChatColor {
+    match team {
+        Team::Red => ChatColor::rgb(255, 64, 64),
+        Team::Blue => ChatColor::rgb(92, 140, 230),
+        Team::Spectator | Team::Other => ChatColor::rgb(255, 255, 255),
+    }
+}
+
+fn parse_hex_digit(c: char) -> Option<u8> {
+    c.to_digit(16).map(|digit| digit as u8)
+}
+
+fn parse_hex_byte(high: char, low: char) -> Option<u8> {
+    Some((parse_hex_digit(high)? << 4) | parse_hex_digit(low)?)
+}
+
+impl ChatSegment {
+    /// Split a raw `SayText2` string into colored segments, resolving the
+    /// `0x03` "team color" control byte using the speaking player's `team`.
+    pub fn parse(text: &str, team: Team) -> Vec<ChatSegment> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut segments = Vec::new();
+        let mut color: Option<ChatColor> = None;
+        let mut run = String::new();
+        let mut i = 0;
+
+        fn flush(segments: &mut Vec<ChatSegment>, run: &mut String, color: Option<ChatColor>) {
+            if !run.is_empty() {
+                segments.push(ChatSegment {
+                    color,
+                    text: std::mem::take(run),
+                });
+            }
+        }
+
+        while i < chars.len() {
+            match chars[i] {
+                CHAT_COLOR_DEFAULT => {
+                    flush(&mut segments, &mut run, color);
+                    color = None;
+                    i += 1;
+                }
+                CHAT_COLOR_TEAM => {
+                    flush(&mut segments, &mut run, color);
+                    color = Some(team_chat_color(team));
+                    i += 1;
+                }
+                CHAT_COLOR_LOCATION => {
+                    flush(&mut segments, &mut run, color);
+                    color = Some(LOCATION_CHAT_COLOR);
+                    i += 1;
+                }
+                CHAT_COLOR_ACHIEVEMENT => {
+                    flush(&mut segments, &mut run, color);
+                    color = Some(ACHIEVEMENT_CHAT_COLOR);
+                    i += 1;
+                }
+                CHAT_COLOR_CUSTOM_RGB
+                    if i + 6 < chars.len()
+                        && chars[i + 1..=i + 6].iter().all(|c| c.is_ascii_hexdigit()) =>
+                {
+                    flush(&mut segments, &mut run, color);
+                    color = Some(ChatColor::rgb(
+                        parse_hex_byte(chars[i + 1], chars[i + 2]).unwrap_or_default(),
+                        parse_hex_byte(chars[i + 3], chars[i + 4]).unwrap_or_default(),
+                        parse_hex_byte(chars[i + 5], chars[i + 6]).unwrap_or_default(),
+                    ));
+                    i += 7;
+                }
+                CHAT_COLOR_CUSTOM_RGBA
+                    if i + 8 < chars.len()
+                        && chars[i + 1..=i + 8].iter().all(|c| c.is_ascii_hexdigit()) =>
+                {
+                    flush(&mut segments, &mut run, color);
+                    color = Some(ChatColor::rgba(
+                        parse_hex_byte(chars[i + 1], chars[i + 2]).unwrap_or_default(),
+                        parse_hex_byte(chars[i + 3], chars[i + 4]).unwrap_or_default(),
+                        parse_hex_byte(chars[i + 5], chars[i + 6]).unwrap_or_default(),
+                        parse_hex_byte(chars[i + 7], chars[i + 8]).unwrap_or_default(),
+                    ));
+                    i += 9;
+                }
+                other => {
+                    run.push(other);
+                    i += 1;
+                }
+            }
+        }
+        flush(&mut segments, &mut run, color);
+
+        segments
+    }
+}
+
+impl ChatMassage {
+    /// Render the colored segments as a string with ANSI SGR escapes, for
+    /// printing faithfully-colored chat to a terminal.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        // `None` here means "nothing emitted yet", distinct from a segment
+        // whose color is `Option::None` (default color), so the very first
+        // segment always gets its leading reset.
+        let mut current: Option<Option<ChatColor>> = None;
+
+        for segment in &self.segments {
+            if current != Some(segment.color) {
+                out.push_str("\x1b[0m");
+                if let Some(color) = segment.color {
+                    out.push_str(&format!("\x1b[38;2;{};{};{}m", color.r, color.g, color.b));
+                }
+                current = Some(segment.color);
+            }
+            out.push_str(&segment.text);
+        }
+        out.push_str("\x1b[0m");
+
+        out
+    }
+}
+
+#[test]
+fn test_chat_segment_parse_team_color() {
+    let text = format!("{}hello {}world", CHAT_COLOR_DEFAULT, CHAT_COLOR_TEAM);
+    let segments = ChatSegment::parse(&text, Team::Red);
+    assert_eq!(
+        segments,
+        vec![
+            ChatSegment {
+                color: None,
+                text: "hello ".to_string(),
+            },
+            ChatSegment {
+                color: Some(team_chat_color(Team::Red)),
+                text: "world".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_chat_segment_parse_custom_rgb() {
+    let text = format!("{}ff00807fred", CHAT_COLOR_CUSTOM_RGB);
+    let segments = ChatSegment::parse(&text, Team::Other);
+    assert_eq!(
+        segments,
+        vec![ChatSegment {
+            color: Some(ChatColor::rgb(0xff, 0x00, 0x80)),
+            text: "7fred".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_chat_massage_from_parts_builds_segments() {
+    let message = ChatMassage::from_parts(
+        ChatMessageKind::Say,
+        "player".to_string(),
+        "hello".to_string(),
+        0,
+        Team::Red,
+    );
+
+    assert_eq!(message.text, "hello");
+    assert_eq!(
+        message.segments,
+        vec![ChatSegment {
+            color: None,
+            text: "hello".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_chat_massage_to_ansi() {
+    let message = ChatMassage {
+        kind: ChatMessageKind::Say,
+        from: "player".to_string(),
+        text: "hi".to_string(),
+        segments: vec![
+            ChatSegment {
+                color: None,
+                text: "hi ".to_string(),
+            },
+            ChatSegment {
+                color: Some(ChatColor::rgb(255, 0, 0)),
+                text: "there".to_string(),
+            },
+        ],
+        tick: 0,
+    };
+
+    assert_eq!(
+        message.to_ansi(),
+        "\x1b[0mhi \x1b[0m\x1b[38;2;255;0;0mthere\x1b[0m"
+    );
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, TryFromPrimitive)]
 #[serde(rename_all = "lowercase")]
 #[repr(u8)]
@@ -252,9 +504,14 @@ pub struct Death {
     pub tick: u32,
 }
 
+/// Sentinel the game event uses for "no assister". `UserId` is itself an
+/// 8-bit wrapper (see `UserId::from`), so there's no wider sentinel for a
+/// protocol-gated check to distinguish.
+const NO_ASSISTER: u32 = 255;
+
 impl Death {
     pub fn from_event(event: &PlayerDeathEvent, tick: u32) -> Self {
-        let assister = if event.assister < (16 * 1024) {
+        let assister = if event.assister < NO_ASSISTER {
             Some(UserId::from(event.assister))
         } else {
             None
@@ -292,10 +549,24 @@ pub struct World {
     pub boundary_max: Vector,
 }
 
+#[test]
+fn test_handle_header_derives_protocol_version() {
+    let mut analyser = Analyser::new();
+    let header = crate::demo::parser::protocolversion::test_header(23);
+
+    analyser.handle_header(&header);
+
+    assert_eq!(analyser.protocol_version, ProtocolVersion(23));
+}
+
 #[derive(Default, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Analyser {
     state: MatchState,
     user_id_map: HashMap<EntityId, UserId>,
+    #[serde(skip)]
+    protocol_version: ProtocolVersion,
+    #[serde(skip)]
+    protocol_version_pinned: bool,
 }
 
 impl MessageHandler for Analyser {
@@ -331,6 +602,12 @@ impl MessageHandler for Analyser {
         }
     }
 
+    fn handle_header(&mut self, header: &Header) {
+        if !self.protocol_version_pinned {
+            self.protocol_version = ProtocolVersion::from_header(header.protocol, header.version);
+        }
+    }
+
     fn into_output(self, _state: &ParserState) -> Self::Output {
         self.state
     }
@@ -347,6 +624,17 @@ impl Analyser {
         Self::default()
     }
 
+    /// Create an analyser pinned to a specific network protocol, overriding
+    /// the version [`MessageHandler::handle_header`] would otherwise derive
+    /// from the demo's own header.
+    pub fn with_protocol_version(version: ProtocolVersion) -> Self {
+        Self {
+            protocol_version: version,
+            protocol_version_pinned: true,
+            ..Self::default()
+        }
+    }
+
     fn handle_user_message(&mut self, message: &UserMessage, tick: u32) {
         if let UserMessage::SayText2(text_message) = message {
             if text_message.kind == ChatMessageKind::NameChange {
@@ -354,9 +642,15 @@ impl Analyser {
                     self.change_name(from, text_message.text.clone());
                 }
             } else {
+                let team = text_message
+                    .from
+                    .as_deref()
+                    .and_then(|from| self.state.users.values().find(|user| user.name == from))
+                    .map(|user| user.team)
+                    .unwrap_or_default();
                 self.state
                     .chat
-                    .push(ChatMassage::from_message(text_message, tick));
+                    .push(ChatMassage::from_message(text_message, tick, team));
             }
         }
     }
@@ -371,7 +665,11 @@ impl Analyser {
         const WIN_REASON_TIME_LIMIT: u8 = 6;
 
         match event {
-            GameEvent::PlayerDeath(event) => self.state.deaths.push(Death::from_event(event, tick)),
+            GameEvent::PlayerDeath(event) => {
+                self.state
+                    .deaths
+                    .push(Death::from_event(event, tick))
+            }
             GameEvent::PlayerSpawn(event) => {
                 let spawn = Spawn::from_event(event, tick);
                 if let Some(user_state) = self.state.users.get_mut(&spawn.user) {
@@ -389,7 +687,8 @@ impl Analyser {
     }
 
     fn parse_user_info(&mut self, text: Option<&str>, data: Option<Stream>) -> ReadResult<()> {
-        if let Some(user_info) = crate::demo::data::UserInfo::parse_from_string_table(text, data)? {
+        if let Some(user_info) = crate::demo::data::UserInfo::parse_from_string_table(text, data)?
+        {
             self.state
                 .users
                 .entry(user_info.user_id)
@@ -0,0 +1,5 @@
+pub mod analyser;
+pub mod protocolversion;
+pub mod scoreboardanalyser;
+pub mod stringdecode;
+pub mod tickexporter;
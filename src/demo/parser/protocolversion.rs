@@ -0,0 +1,49 @@
+/// The TF2 network protocol a demo was recorded against, derived from the
+/// demo header's `protocol`/`version` fields via
+/// [`MessageHandler::handle_header`](super::handler::MessageHandler::handle_header).
+///
+/// Message and field layouts can in principle shift between protocol
+/// versions, but nothing in this crate branches on that yet: we don't have a
+/// sourced, verified case of a layout actually changing, and guessing one
+/// would be worse than not handling it. This type (and
+/// `Analyser`/`ScoreboardAnalyser`'s `with_protocol_version`) exists as the
+/// hook version-gated decoding would consult once such a case turns up and
+/// is confirmed against real demo data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProtocolVersion(pub u32);
+
+impl ProtocolVersion {
+    /// The protocol version this crate originally targeted, used when no
+    /// version information is available.
+    pub const LATEST: ProtocolVersion = ProtocolVersion(24);
+
+    pub fn from_header(protocol: u32, _version: u32) -> Self {
+        ProtocolVersion(protocol)
+    }
+}
+
+impl Default for ProtocolVersion {
+    fn default() -> Self {
+        ProtocolVersion::LATEST
+    }
+}
+
+/// A minimal demo header fixture for tests that only care about
+/// `protocol`/`version`, shared so `analyser` and `scoreboardanalyser`
+/// don't each carry their own copy of every other field.
+#[cfg(test)]
+pub(crate) fn test_header(protocol: u32) -> crate::demo::header::Header {
+    crate::demo::header::Header {
+        demo_type: String::new(),
+        version: 0,
+        protocol,
+        server: String::new(),
+        nick: String::new(),
+        map: String::new(),
+        game: String::new(),
+        duration: 0.0,
+        ticks: 0,
+        frames: 0,
+        signon: 0,
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::ops::Index;
+
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+
+use super::analyser::{Class, Death, Spawn, UserId};
+use super::protocolversion::ProtocolVersion;
+use crate::demo::gameevent_gen::GameEvent;
+use crate::demo::header::Header;
+use crate::demo::message::{Message, MessageType};
+use crate::demo::parser::handler::{BorrowMessageHandler, MessageHandler};
+use crate::ParserState;
+
+/// Time spent playing each class, in seconds, keyed the same way as
+/// [`super::analyser::ClassList`] but tracking duration instead of spawn count.
+#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "HashMap<Class, f32>")]
+pub struct ClassTime([f32; 10]);
+
+impl ClassTime {
+    fn add(&mut self, class: Class, seconds: f32) {
+        self.0[class as u8 as usize] += seconds;
+    }
+}
+
+impl Index<Class> for ClassTime {
+    type Output = f32;
+
+    fn index(&self, class: Class) -> &Self::Output {
+        &self.0[class as u8 as usize]
+    }
+}
+
+impl Serialize for ClassTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let count = self.0.iter().filter(|seconds| **seconds > 0.0).count();
+        let mut classes = serializer.serialize_map(Some(count))?;
+        for (class, seconds) in self.0.iter().copied().enumerate() {
+            if seconds > 0.0 {
+                classes.serialize_entry(&Class::new(class), &seconds)?;
+            }
+        }
+
+        classes.end()
+    }
+}
+
+impl From<HashMap<Class, f32>> for ClassTime {
+    fn from(map: HashMap<Class, f32>) -> Self {
+        let mut time = ClassTime::default();
+
+        for (class, seconds) in map.into_iter() {
+            time.add(class, seconds);
+        }
+
+        time
+    }
+}
+
+/// Per-player aggregate stats, matching the schema demos.tf exposes for an
+/// uploaded demo's scoreboard.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerScoreboard {
+    pub kills: u32,
+    pub assists: u32,
+    pub deaths: u32,
+    pub longest_killstreak: u32,
+    #[serde(skip)]
+    current_killstreak: u32,
+    pub class_time: ClassTime,
+}
+
+/// Aggregates kills, deaths, assists, killstreaks and per-class playtime into
+/// a per-player scoreboard, ready for upload/leaderboard tooling without a
+/// second pass over [`super::analyser::MatchState`].
+#[derive(Default, Debug)]
+pub struct ScoreboardAnalyser {
+    players: HashMap<UserId, PlayerScoreboard>,
+    current_class: HashMap<UserId, (Class, u32)>,
+    interval_per_tick: f32,
+    last_tick: u32,
+    protocol_version: ProtocolVersion,
+    protocol_version_pinned: bool,
+}
+
+impl ScoreboardAnalyser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a scoreboard analyser pinned to a specific network protocol,
+    /// overriding the version [`MessageHandler::handle_header`] would
+    /// otherwise derive from the demo's own header. Matches
+    /// [`super::analyser::Analyser::with_protocol_version`].
+    pub fn with_protocol_version(version: ProtocolVersion) -> Self {
+        Self {
+            protocol_version: version,
+            protocol_version_pinned: true,
+            ..Self::default()
+        }
+    }
+
+    fn handle_event(&mut self, event: &GameEvent, tick: u32) {
+        match event {
+            GameEvent::PlayerDeath(event) => self.handle_death(&Death::from_event(event, tick)),
+            GameEvent::PlayerSpawn(event) => self.handle_spawn(&Spawn::from_event(event, tick)),
+            _ => {}
+        }
+    }
+
+    fn handle_spawn(&mut self, spawn: &Spawn) {
+        if let Some((class, start_tick)) = self
+            .current_class
+            .insert(spawn.user, (spawn.class, spawn.tick))
+        {
+            self.add_class_time(spawn.user, class, start_tick, spawn.tick);
+        }
+    }
+
+    fn handle_death(&mut self, death: &Death) {
+        let victim = self.players.entry(death.victim).or_default();
+        victim.deaths += 1;
+        victim.current_killstreak = 0;
+
+        // Suicides (killer == victim) don't count toward the killer's kills.
+        if death.killer != death.victim {
+            let killer = self.players.entry(death.killer).or_default();
+            killer.kills += 1;
+            killer.current_killstreak += 1;
+            killer.longest_killstreak = killer.longest_killstreak.max(killer.current_killstreak);
+        }
+
+        if let Some(assister) = death.assister {
+            self.players.entry(assister).or_default().assists += 1;
+        }
+    }
+
+    fn add_class_time(&mut self, user: UserId, class: Class, start_tick: u32, end_tick: u32) {
+        let seconds = end_tick.saturating_sub(start_tick) as f32 * self.interval_per_tick;
+        self.players
+            .entry(user)
+            .or_default()
+            .class_time
+            .add(class, seconds);
+    }
+}
+
+impl MessageHandler for ScoreboardAnalyser {
+    type Output = HashMap<UserId, PlayerScoreboard>;
+
+    fn does_handle(message_type: MessageType) -> bool {
+        matches!(
+            message_type,
+            MessageType::GameEvent | MessageType::ServerInfo
+        )
+    }
+
+    fn handle_message(&mut self, message: &Message, tick: u32) {
+        self.last_tick = tick;
+        match message {
+            Message::ServerInfo(message) => self.interval_per_tick = message.interval_per_tick,
+            Message::GameEvent(message) => self.handle_event(&message.event, tick),
+            _ => {}
+        }
+    }
+
+    fn handle_header(&mut self, header: &Header) {
+        if !self.protocol_version_pinned {
+            self.protocol_version = ProtocolVersion::from_header(header.protocol, header.version);
+        }
+    }
+
+    fn into_output(mut self, _state: &ParserState) -> Self::Output {
+        // Credit whichever class each player spawned into last with the time
+        // up to the final tick, since no further spawn will close it out.
+        let last_tick = self.last_tick;
+        let pending: Vec<(UserId, Class, u32)> = self
+            .current_class
+            .iter()
+            .map(|(user, (class, start_tick))| (*user, *class, *start_tick))
+            .collect();
+        for (user, class, start_tick) in pending {
+            self.add_class_time(user, class, start_tick, last_tick);
+        }
+
+        self.players
+    }
+}
+
+impl BorrowMessageHandler for ScoreboardAnalyser {
+    fn borrow_output(&self, _state: &ParserState) -> &Self::Output {
+        &self.players
+    }
+}
+
+#[test]
+fn test_handle_header_derives_protocol_version() {
+    let mut analyser = ScoreboardAnalyser::new();
+    let header = super::protocolversion::test_header(23);
+
+    analyser.handle_header(&header);
+
+    assert_eq!(analyser.protocol_version, ProtocolVersion(23));
+}
+
+#[test]
+fn test_scoreboard_killstreak_and_suicide() {
+    let mut analyser = ScoreboardAnalyser::new();
+    let killer = UserId(1);
+    let victim = UserId(2);
+
+    analyser.handle_death(&Death {
+        weapon: "scattergun".to_string(),
+        victim,
+        assister: None,
+        killer,
+        tick: 1,
+    });
+    analyser.handle_death(&Death {
+        weapon: "scattergun".to_string(),
+        victim,
+        assister: None,
+        killer,
+        tick: 2,
+    });
+    analyser.handle_death(&Death {
+        weapon: "world".to_string(),
+        victim: killer,
+        assister: None,
+        killer,
+        tick: 3,
+    });
+
+    let stats = analyser.players.get(&killer).unwrap();
+    assert_eq!(stats.kills, 2);
+    assert_eq!(stats.longest_killstreak, 2);
+    assert_eq!(stats.deaths, 1);
+}
+
+#[test]
+fn test_scoreboard_class_time() {
+    use super::analyser::Team;
+
+    let mut analyser = ScoreboardAnalyser::new();
+    analyser.interval_per_tick = 0.5;
+    let user = UserId(1);
+
+    analyser.handle_spawn(&Spawn {
+        user,
+        class: Class::Scout,
+        team: Team::Red,
+        tick: 0,
+    });
+    analyser.handle_spawn(&Spawn {
+        user,
+        class: Class::Soldier,
+        team: Team::Red,
+        tick: 10,
+    });
+
+    let stats = analyser.players.get(&user).unwrap();
+    assert_eq!(stats.class_time[Class::Scout], 5.0);
+}
@@ -0,0 +1,135 @@
+//! Decodes raw, possibly non-UTF-8 bytes into a `String`. `Analyser` only
+//! ever sees names and chat text after they've already been converted to
+//! `String` upstream (in `demo::data` and `demo::message::usermessage`), so
+//! it has no raw bytes left to run this on; this is exposed for callers
+//! further up the pipeline, or custom `MessageHandler`s that read
+//! `StringTableEntry`/user message bytes directly.
+
+use std::error::Error;
+use std::fmt;
+
+/// How to decode strings (player names, chat text) that may not be valid
+/// UTF-8 — TF2 clients are free to send CP1252/Latin-1 bytes in names and
+/// chat/kill feed strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringDecoding {
+    /// Require valid UTF-8 and error out on anything else, for callers that
+    /// need byte-exact round-tripping.
+    Strict,
+    /// Decode valid UTF-8 as-is, falling back to a byte-wise CP1252 decode
+    /// for invalid sequences so no data is lost or mangled.
+    Lossy,
+}
+
+impl Default for StringDecoding {
+    fn default() -> Self {
+        StringDecoding::Lossy
+    }
+}
+
+/// A byte sequence that was not valid UTF-8 while decoding in
+/// [`StringDecoding::Strict`] mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEncoding {
+    pub bytes: Vec<u8>,
+}
+
+impl fmt::Display for InvalidEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid UTF-8 bytes in strict decode mode: {:?}",
+            self.bytes
+        )
+    }
+}
+
+impl Error for InvalidEncoding {}
+
+/// Decode `bytes` according to `mode`: UTF-8 is always tried first, with
+/// `Lossy` falling back to CP1252 instead of the usual U+FFFD replacement so
+/// that names like `"Caf\u{e9}"` and clan tags survive round-tripping.
+pub fn decode(bytes: &[u8], mode: StringDecoding) -> Result<String, InvalidEncoding> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(text.to_string()),
+        Err(_) => match mode {
+            StringDecoding::Strict => Err(InvalidEncoding {
+                bytes: bytes.to_vec(),
+            }),
+            StringDecoding::Lossy => Ok(decode_cp1252(bytes)),
+        },
+    }
+}
+
+fn decode_cp1252(mut bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                out.push_str(valid);
+                return out;
+            }
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+                out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                let invalid_len = err.error_len().unwrap_or(rest.len());
+                out.extend(rest[..invalid_len].iter().copied().map(cp1252_char));
+                bytes = &rest[invalid_len..];
+            }
+        }
+    }
+}
+
+/// Map a single CP1252 byte to its Unicode scalar. `0x00..=0x7F` and
+/// `0xA0..=0xFF` are identical to Latin-1; `0x80..=0x9F` hold the extra
+/// punctuation/currency characters CP1252 adds over Latin-1.
+fn cp1252_char(byte: u8) -> char {
+    match byte {
+        0x80 => '\u{20AC}',
+        0x82 => '\u{201A}',
+        0x83 => '\u{0192}',
+        0x84 => '\u{201E}',
+        0x85 => '\u{2026}',
+        0x86 => '\u{2020}',
+        0x87 => '\u{2021}',
+        0x88 => '\u{02C6}',
+        0x89 => '\u{2030}',
+        0x8A => '\u{0160}',
+        0x8B => '\u{2039}',
+        0x8C => '\u{0152}',
+        0x8E => '\u{017D}',
+        0x91 => '\u{2018}',
+        0x92 => '\u{2019}',
+        0x93 => '\u{201C}',
+        0x94 => '\u{201D}',
+        0x95 => '\u{2022}',
+        0x96 => '\u{2013}',
+        0x97 => '\u{2014}',
+        0x98 => '\u{02DC}',
+        0x99 => '\u{2122}',
+        0x9A => '\u{0161}',
+        0x9B => '\u{203A}',
+        0x9C => '\u{0153}',
+        0x9E => '\u{017E}',
+        0x9F => '\u{0178}',
+        other => other as char,
+    }
+}
+
+#[test]
+fn test_decode_lossy_cp1252_fallback() {
+    let bytes = [b'C', b'a', b'f', 0xE9];
+    assert_eq!(decode(&bytes, StringDecoding::Lossy).unwrap(), "Caf\u{e9}");
+}
+
+#[test]
+fn test_decode_strict_rejects_invalid_utf8() {
+    let bytes = [0xE9];
+    assert!(decode(&bytes, StringDecoding::Strict).is_err());
+}
+
+#[test]
+fn test_decode_prefers_valid_utf8() {
+    let bytes = "Café".as_bytes();
+    assert_eq!(decode(bytes, StringDecoding::Lossy).unwrap(), "Café");
+}
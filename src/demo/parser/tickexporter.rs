@@ -0,0 +1,157 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::demo::header::Header;
+use crate::demo::parser::analyser::{Class, Team};
+use crate::demo::parser::gamestateanalyser::{GameState, Player};
+use crate::demo::vector::Vector;
+
+/// Public record schema for [`TickExporter`]'s NDJSON output, kept in its own
+/// module the way the crate's other JSON API types are.
+pub mod schema {
+    use super::*;
+
+    /// One line of [`TickExporter`]'s NDJSON stream: a header record once,
+    /// followed by one tick record per exported tick.
+    #[derive(Debug, Clone, Serialize)]
+    #[serde(tag = "type", rename_all = "camelCase")]
+    pub enum TickRecord {
+        Header(HeaderRecord),
+        Tick(TickSnapshot),
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct HeaderRecord {
+        pub map: String,
+        pub server: String,
+        pub duration: f32,
+        pub ticks: u32,
+    }
+
+    impl From<&Header> for HeaderRecord {
+        fn from(header: &Header) -> Self {
+            HeaderRecord {
+                map: header.map.clone(),
+                server: header.server.clone(),
+                duration: header.duration,
+                ticks: header.ticks,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TickSnapshot {
+        pub tick: u32,
+        pub players: Vec<PlayerSnapshot>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct PlayerSnapshot {
+        pub name: String,
+        pub position: Vector,
+        pub pitch: f32,
+        pub yaw: f32,
+        pub class: Class,
+        pub team: Team,
+        pub health: u16,
+    }
+}
+
+use schema::{HeaderRecord, PlayerSnapshot, TickRecord, TickSnapshot};
+
+/// Streams one NDJSON record per tick to any [`Write`] sink instead of
+/// buffering the whole demo in memory. Call [`TickExporter::write_header`]
+/// once, then [`TickExporter::write_tick`] for each tick yielded while
+/// driving a [`super::DemoTicker`].
+pub struct TickExporter<W: Write> {
+    sink: W,
+    stride: u32,
+}
+
+impl<W: Write> TickExporter<W> {
+    /// Export every tick.
+    pub fn new(sink: W) -> Self {
+        TickExporter { sink, stride: 1 }
+    }
+
+    /// Export only every `stride`th tick, for downsampled movie/heatmap data.
+    pub fn with_stride(sink: W, stride: u32) -> Self {
+        TickExporter {
+            sink,
+            stride: stride.max(1),
+        }
+    }
+
+    pub fn write_header(&mut self, header: &Header) -> io::Result<()> {
+        self.write_record(&TickRecord::Header(HeaderRecord::from(header)))
+    }
+
+    pub fn write_tick(&mut self, state: &GameState) -> io::Result<()> {
+        let tick = u32::from(state.tick);
+        let players = state.players.iter().filter_map(player_snapshot).collect();
+        self.write_tick_snapshot(tick, players)
+    }
+
+    fn write_tick_snapshot(&mut self, tick: u32, players: Vec<PlayerSnapshot>) -> io::Result<()> {
+        if tick % self.stride != 0 {
+            return Ok(());
+        }
+
+        self.write_record(&TickRecord::Tick(TickSnapshot { tick, players }))
+    }
+
+    fn write_record(&mut self, record: &TickRecord) -> io::Result<()> {
+        serde_json::to_writer(&mut self.sink, record)?;
+        self.sink.write_all(b"\n")
+    }
+}
+
+fn player_snapshot(player: &Player) -> Option<PlayerSnapshot> {
+    let info = player.info.as_ref()?;
+    Some(PlayerSnapshot {
+        name: info.name.clone(),
+        position: player.position,
+        pitch: player.pitch_angle,
+        yaw: player.view_angle,
+        class: player.class,
+        team: player.team,
+        health: player.health,
+    })
+}
+
+#[test]
+fn test_write_tick_snapshot_is_one_json_line() {
+    let mut exporter = TickExporter::new(Vec::new());
+    exporter
+        .write_tick_snapshot(
+            3,
+            vec![PlayerSnapshot {
+                name: "player".to_string(),
+                position: Vector::default(),
+                pitch: 0.0,
+                yaw: 0.0,
+                class: Class::Scout,
+                team: Team::Red,
+                health: 125,
+            }],
+        )
+        .unwrap();
+
+    let output = String::from_utf8(exporter.sink).unwrap();
+    assert_eq!(output.matches('\n').count(), 1);
+    assert!(output.contains("\"tick\":3"));
+}
+
+#[test]
+fn test_stride_skips_ticks() {
+    let mut exporter = TickExporter::with_stride(Vec::new(), 10);
+    exporter.write_tick_snapshot(5, Vec::new()).unwrap();
+    assert!(exporter.sink.is_empty());
+
+    exporter.write_tick_snapshot(10, Vec::new()).unwrap();
+    assert_eq!(exporter.sink.iter().filter(|&&b| b == b'\n').count(), 1);
+}